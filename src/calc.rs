@@ -0,0 +1,350 @@
+/// The outcome of evaluating a calculator/conversion query: what to show
+/// in the answer row and what to put on the clipboard when it's clicked.
+pub struct CalcResult {
+    pub display: String,
+    pub value: String,
+}
+
+/// Heuristically decides whether `query` looks like an arithmetic
+/// expression or unit conversion rather than an app search: an explicit
+/// `= ` trigger prefix, a leading digit or `(`, or the presence of an
+/// operator alongside a digit.
+pub fn looks_like_expression(query: &str) -> bool {
+    let trimmed = query.trim();
+    if trimmed.is_empty() {
+        return false;
+    }
+
+    if let Some(rest) = trimmed.strip_prefix('=') {
+        return !rest.trim().is_empty();
+    }
+
+    let first = trimmed.chars().next().unwrap();
+    if first.is_ascii_digit() || first == '(' {
+        return true;
+    }
+
+    let has_operator = trimmed.chars().any(|c| matches!(c, '+' | '-' | '*' | '/' | '%' | '^'));
+    let has_digit = trimmed.chars().any(|c| c.is_ascii_digit());
+    has_operator && has_digit
+}
+
+/// Evaluates `query` as a calculator expression or unit conversion,
+/// returning `None` if it doesn't parse as one.
+pub fn evaluate(query: &str) -> Option<CalcResult> {
+    let trimmed = query.trim().strip_prefix('=').unwrap_or(query).trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    if let Some(result) = evaluate_conversion(trimmed) {
+        return Some(result);
+    }
+
+    let value = Parser::new(trimmed).parse()?;
+    let display = format_number(value);
+    Some(CalcResult { display: display.clone(), value: display })
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract().abs() < 1e-9 {
+        format!("{}", value as i64)
+    } else {
+        let rounded = (value * 1e6).round() / 1e6;
+        rounded.to_string()
+    }
+}
+
+/// Converts `<number><unit> to <unit>`, e.g. `10 km to mi` or `98.6 f to
+/// c`, or `<number> to <base>` for base conversions like `255 to hex`.
+/// Case-insensitive; the `to` keyword is required.
+fn evaluate_conversion(input: &str) -> Option<CalcResult> {
+    let lower = input.to_lowercase();
+    let (lhs, rhs_unit) = lower.split_once(" to ")?;
+    let rhs_unit = rhs_unit.trim();
+    let lhs = lhs.trim();
+
+    if matches!(rhs_unit, "hex" | "hexadecimal" | "bin" | "binary" | "dec" | "decimal") {
+        let amount = parse_number_literal(lhs)?;
+        let display = format_in_base(amount, rhs_unit)?;
+        return Some(CalcResult { display: display.clone(), value: display });
+    }
+
+    let split_at = lhs.find(|c: char| c.is_alphabetic())?;
+    let (number_part, lhs_unit) = lhs.split_at(split_at);
+    let amount: f64 = number_part.trim().parse().ok()?;
+    let lhs_unit = lhs_unit.trim();
+
+    let converted = convert_unit(amount, lhs_unit, rhs_unit)?;
+    let display = format!("{} {}", format_number(converted), rhs_unit);
+    Some(CalcResult { display: display.clone(), value: format_number(converted) })
+}
+
+/// Parses a bare number literal for base conversion: `0x`/`0b`-prefixed
+/// hex/binary, or a plain decimal integer.
+fn parse_number_literal(input: &str) -> Option<f64> {
+    if let Some(hex) = input.strip_prefix("0x") {
+        return i64::from_str_radix(hex, 16).ok().map(|v| v as f64);
+    }
+    if let Some(bin) = input.strip_prefix("0b") {
+        return i64::from_str_radix(bin, 2).ok().map(|v| v as f64);
+    }
+    input.parse().ok()
+}
+
+/// Formats an integral `amount` in the requested base. Returns `None` if
+/// `amount` isn't (close enough to) an integer, since hex/bin notation
+/// doesn't have a fractional form here.
+fn format_in_base(amount: f64, base: &str) -> Option<String> {
+    let int_value = amount.round() as i64;
+    if (int_value as f64 - amount).abs() > 1e-9 {
+        return None;
+    }
+
+    Some(match base {
+        "hex" | "hexadecimal" => format!("0x{int_value:x}"),
+        "bin" | "binary" => format!("0b{int_value:b}"),
+        "dec" | "decimal" => int_value.to_string(),
+        _ => return None,
+    })
+}
+
+/// A handful of common units, expressed as a factor to a base unit per
+/// dimension, plus temperature as a special case since it isn't a pure
+/// scale factor.
+fn convert_unit(amount: f64, from: &str, to: &str) -> Option<f64> {
+    if matches!(from, "c" | "celsius") || matches!(to, "c" | "celsius")
+        || matches!(from, "f" | "fahrenheit") || matches!(to, "f" | "fahrenheit") {
+        return convert_temperature(amount, from, to);
+    }
+
+    let from_factor = length_or_mass_factor(from)?;
+    let to_factor = length_or_mass_factor(to)?;
+    Some(amount * from_factor / to_factor)
+}
+
+fn length_or_mass_factor(unit: &str) -> Option<f64> {
+    Some(match unit {
+        "m" | "meter" | "meters" => 1.0,
+        "km" | "kilometer" | "kilometers" => 1000.0,
+        "mi" | "mile" | "miles" => 1609.344,
+        "ft" | "foot" | "feet" => 0.3048,
+        "cm" | "centimeter" | "centimeters" => 0.01,
+        "kg" | "kilogram" | "kilograms" => 1.0,
+        "lb" | "lbs" | "pound" | "pounds" => 0.45359237,
+        "g" | "gram" | "grams" => 0.001,
+        _ => return None,
+    })
+}
+
+fn convert_temperature(amount: f64, from: &str, to: &str) -> Option<f64> {
+    let celsius = match from {
+        "c" | "celsius" => amount,
+        "f" | "fahrenheit" => (amount - 32.0) * 5.0 / 9.0,
+        _ => return None,
+    };
+
+    Some(match to {
+        "c" | "celsius" => celsius,
+        "f" | "fahrenheit" => celsius * 9.0 / 5.0 + 32.0,
+        _ => return None,
+    })
+}
+
+/// A small recursive-descent parser/evaluator for `+ - * / % ^` with
+/// parentheses and `0x`/`0b` integer literals.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        Parser { chars: input.chars().filter(|c| !c.is_whitespace()).collect(), pos: 0 }
+    }
+
+    fn parse(&mut self) -> Option<f64> {
+        let value = self.parse_expr()?;
+        if self.pos != self.chars.len() {
+            return None;
+        }
+        Some(value)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn parse_expr(&mut self) -> Option<f64> {
+        let mut value = self.parse_term()?;
+        loop {
+            match self.peek() {
+                Some('+') => {
+                    self.pos += 1;
+                    value += self.parse_term()?;
+                }
+                Some('-') => {
+                    self.pos += 1;
+                    value -= self.parse_term()?;
+                }
+                _ => break,
+            }
+        }
+        Some(value)
+    }
+
+    fn parse_term(&mut self) -> Option<f64> {
+        let mut value = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some('*') => {
+                    self.pos += 1;
+                    value *= self.parse_unary()?;
+                }
+                Some('/') => {
+                    self.pos += 1;
+                    let divisor = self.parse_unary()?;
+                    if divisor == 0.0 {
+                        return None;
+                    }
+                    value /= divisor;
+                }
+                Some('%') => {
+                    self.pos += 1;
+                    let divisor = self.parse_unary()?;
+                    if divisor == 0.0 {
+                        return None;
+                    }
+                    value %= divisor;
+                }
+                _ => break,
+            }
+        }
+        Some(value)
+    }
+
+    /// Unary minus binds looser than `^`, so `-3^2` is `-(3^2)`, not
+    /// `(-3)^2` — matching every other calculator's convention.
+    fn parse_unary(&mut self) -> Option<f64> {
+        if self.peek() == Some('-') {
+            self.pos += 1;
+            return Some(-self.parse_unary()?);
+        }
+        self.parse_power()
+    }
+
+    fn parse_power(&mut self) -> Option<f64> {
+        let base = self.parse_primary()?;
+        if self.peek() == Some('^') {
+            self.pos += 1;
+            let exponent = self.parse_unary()?;
+            return Some(base.powf(exponent));
+        }
+        Some(base)
+    }
+
+    fn parse_primary(&mut self) -> Option<f64> {
+        if self.peek() == Some('(') {
+            self.pos += 1;
+            let value = self.parse_expr()?;
+            if self.peek() != Some(')') {
+                return None;
+            }
+            self.pos += 1;
+            return Some(value);
+        }
+
+        self.parse_number()
+    }
+
+    fn parse_number(&mut self) -> Option<f64> {
+        let start = self.pos;
+
+        if self.chars[start..].starts_with(&['0', 'x']) {
+            self.pos += 2;
+            let digits_start = self.pos;
+            while self.peek().map(|c| c.is_ascii_hexdigit()).unwrap_or(false) {
+                self.pos += 1;
+            }
+            if self.pos == digits_start {
+                return None;
+            }
+            let digits: String = self.chars[digits_start..self.pos].iter().collect();
+            return i64::from_str_radix(&digits, 16).ok().map(|v| v as f64);
+        }
+
+        if self.chars[start..].starts_with(&['0', 'b']) {
+            self.pos += 2;
+            let digits_start = self.pos;
+            while matches!(self.peek(), Some('0') | Some('1')) {
+                self.pos += 1;
+            }
+            if self.pos == digits_start {
+                return None;
+            }
+            let digits: String = self.chars[digits_start..self.pos].iter().collect();
+            return i64::from_str_radix(&digits, 2).ok().map(|v| v as f64);
+        }
+
+        while self.peek().map(|c| c.is_ascii_digit() || c == '.').unwrap_or(false) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return None;
+        }
+
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> f64 {
+        Parser::new(input).parse().unwrap()
+    }
+
+    #[test]
+    fn unary_minus_binds_looser_than_power() {
+        assert_eq!(eval("-3^2"), -9.0);
+        assert_eq!(eval("2^-2"), 0.25);
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        assert_eq!(eval("2^3^2"), 512.0);
+    }
+
+    #[test]
+    fn division_and_mod_by_zero_fail() {
+        assert!(Parser::new("1/0").parse().is_none());
+        assert!(Parser::new("1%0").parse().is_none());
+    }
+
+    #[test]
+    fn hex_and_binary_literals() {
+        assert_eq!(eval("0xff"), 255.0);
+        assert_eq!(eval("0b1010"), 10.0);
+    }
+
+    #[test]
+    fn converts_length_units() {
+        let result = evaluate_conversion("10 km to mi").unwrap();
+        assert_eq!(result.display, "6.213712 mi");
+    }
+
+    #[test]
+    fn converts_temperature() {
+        let result = evaluate_conversion("98.6 f to c").unwrap();
+        assert_eq!(result.display, "37 c");
+    }
+
+    #[test]
+    fn converts_decimal_to_hex_and_back() {
+        assert_eq!(evaluate_conversion("255 to hex").unwrap().display, "0xff");
+        assert_eq!(evaluate_conversion("0xff to dec").unwrap().display, "255");
+        assert_eq!(evaluate_conversion("1010 to bin").unwrap().display, "0b1111110010");
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::OnceLock;
+
+/// Well-known session-bus name lacker owns so a second invocation can find
+/// (and toggle) the first instead of starting from scratch.
+pub const SERVICE_NAME: &str = "dpdns.org.pisekpiskovec.lacker";
+const OBJECT_PATH: &str = "/dpdns/org/pisekpiskovec/lacker";
+const INTERFACE_NAME: &str = "dpdns.org.pisekpiskovec.lacker.Launcher";
+
+/// A request coming in over DBus, forwarded into the GTK main loop via a
+/// `glib::MainContext` channel since the exported object is served on its
+/// own thread and GTK widgets aren't `Send`.
+#[derive(Clone, Copy, Debug)]
+pub enum LauncherCommand {
+    Show,
+    Hide,
+    Toggle,
+    Quit,
+    Rescan,
+}
+
+struct LauncherInterface {
+    sender: glib::Sender<LauncherCommand>,
+}
+
+#[zbus::interface(name = "dpdns.org.pisekpiskovec.lacker.Launcher")]
+impl LauncherInterface {
+    fn show(&self) {
+        let _ = self.sender.send(LauncherCommand::Show);
+    }
+
+    fn hide(&self) {
+        let _ = self.sender.send(LauncherCommand::Hide);
+    }
+
+    fn toggle(&self) {
+        let _ = self.sender.send(LauncherCommand::Toggle);
+    }
+
+    fn quit(&self) {
+        let _ = self.sender.send(LauncherCommand::Quit);
+    }
+
+    fn rescan(&self) {
+        let _ = self.sender.send(LauncherCommand::Rescan);
+    }
+}
+
+// Held for the life of the process: dropping it would release the
+// well-known name and stop serving the object.
+static CONNECTION: OnceLock<zbus::blocking::Connection> = OnceLock::new();
+
+/// Tries to become the single lacker instance by owning `SERVICE_NAME` on
+/// the session bus and serving `Show`/`Hide`/`Toggle`/`Quit`/`Rescan`,
+/// each forwarded into `sender` for the GTK main loop to act on.
+///
+/// Returns `true` if this process became the primary instance and should
+/// continue starting up. If the name is already owned, instead calls
+/// `Toggle` on the existing instance and returns `false` so the caller can
+/// exit immediately.
+pub fn acquire_or_toggle_existing(sender: glib::Sender<LauncherCommand>) -> bool {
+    let interface = LauncherInterface { sender };
+
+    let built = zbus::blocking::connection::Builder::session()
+        .and_then(|builder| builder.name(SERVICE_NAME))
+        .and_then(|builder| builder.serve_at(OBJECT_PATH, interface))
+        .and_then(|builder| builder.build());
+
+    match built {
+        Ok(connection) => {
+            let _ = CONNECTION.set(connection);
+            true
+        }
+        Err(_) => {
+            toggle_existing_instance();
+            false
+        }
+    }
+}
+
+fn toggle_existing_instance() {
+    let Ok(connection) = zbus::blocking::Connection::session() else {
+        return;
+    };
+
+    let _ = connection.call_method(
+        Some(SERVICE_NAME),
+        OBJECT_PATH,
+        Some(INTERFACE_NAME),
+        "Toggle",
+        &(),
+    );
+}
@@ -0,0 +1,213 @@
+/// Splits a Desktop Entry `Exec=` value into argv tokens, honoring the
+/// spec's quoting rules: whitespace separates tokens except inside double
+/// quotes, and inside double quotes a backslash only escapes `"`, `` ` ``,
+/// `$`, and `\` itself (any other character after a backslash is left
+/// as-is, backslash included).
+fn unquote_exec(exec: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut has_current = false;
+    let mut chars = exec.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            if has_current {
+                tokens.push(std::mem::take(&mut current));
+                has_current = false;
+            }
+            continue;
+        }
+
+        if c == '"' {
+            has_current = true;
+            while let Some(&next) = chars.peek() {
+                if next == '"' {
+                    chars.next();
+                    break;
+                }
+                if next == '\\' {
+                    chars.next();
+                    match chars.peek() {
+                        Some(&escaped) if matches!(escaped, '"' | '`' | '$' | '\\') => {
+                            current.push(escaped);
+                            chars.next();
+                        }
+                        _ => current.push('\\'),
+                    }
+                } else {
+                    current.push(next);
+                    chars.next();
+                }
+            }
+            continue;
+        }
+
+        has_current = true;
+        current.push(c);
+    }
+
+    if has_current {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// The values substituted for field codes, per the Desktop Entry spec.
+struct FieldCodeContext<'a> {
+    name: &'a str,
+    icon: Option<&'a str>,
+    desktop_file_path: &'a str,
+}
+
+/// Expands field codes in an already-unquoted token list: `%f %F %u %U`
+/// are dropped since this launcher never passes files, `%i` becomes
+/// `--icon <icon>` when an `Icon=` key is set, `%c` becomes the
+/// (translated) name, `%k` becomes the `.desktop` file's own path, and the
+/// deprecated `%d %D %n %N %v %m` codes are stripped.
+fn expand_field_codes(tokens: Vec<String>, ctx: &FieldCodeContext) -> Vec<String> {
+    let mut expanded = Vec::with_capacity(tokens.len());
+
+    for token in tokens {
+        match token.as_str() {
+            "%f" | "%F" | "%u" | "%U" | "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => {}
+            "%i" => {
+                if let Some(icon) = ctx.icon {
+                    expanded.push("--icon".to_string());
+                    expanded.push(icon.to_string());
+                }
+            }
+            "%c" => expanded.push(ctx.name.to_string()),
+            "%k" => expanded.push(ctx.desktop_file_path.to_string()),
+            _ => expanded.push(token),
+        }
+    }
+
+    expanded
+}
+
+fn command_exists(cmd: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(cmd).is_file()))
+        .unwrap_or(false)
+}
+
+/// Picks the terminal emulator to wrap `Terminal=true` entries in: the
+/// user's `$TERMINAL`, then `x-terminal-emulator` (the Debian alternative),
+/// then a fallback list of common emulators.
+fn resolve_terminal() -> String {
+    if let Ok(term) = std::env::var("TERMINAL") {
+        if !term.is_empty() {
+            return term;
+        }
+    }
+
+    for candidate in ["x-terminal-emulator", "alacritty", "kitty", "foot", "gnome-terminal", "konsole", "xterm"] {
+        if command_exists(candidate) {
+            return candidate.to_string();
+        }
+    }
+
+    "xterm".to_string()
+}
+
+/// The flag a terminal emulator expects before the command to run. Most
+/// accept `-e`; `gnome-terminal` requires `--` so its own argument parser
+/// doesn't try to interpret the wrapped command's flags; `kitty` takes no
+/// flag at all, treating its trailing positional args as the command.
+fn terminal_exec_flag(terminal: &str) -> Option<&'static str> {
+    if terminal.ends_with("gnome-terminal") {
+        Some("--")
+    } else if terminal.ends_with("kitty") {
+        None
+    } else {
+        Some("-e")
+    }
+}
+
+/// A fully resolved command ready to spawn directly (no `sh -c`).
+pub struct LaunchSpec {
+    pub program: String,
+    pub args: Vec<String>,
+    pub working_dir: Option<String>,
+}
+
+/// Parses an `Exec=` value into a spawnable [`LaunchSpec`], unquoting it,
+/// expanding field codes, and wrapping it in the user's terminal emulator
+/// when `terminal` is true (per `Terminal=true`).
+pub fn build_launch_spec(
+    exec: &str,
+    name: &str,
+    icon: Option<&str>,
+    desktop_file_path: &str,
+    terminal: bool,
+    working_dir: Option<&str>,
+) -> Option<LaunchSpec> {
+    let ctx = FieldCodeContext { name, icon, desktop_file_path };
+    let mut argv = expand_field_codes(unquote_exec(exec), &ctx);
+    if argv.is_empty() {
+        return None;
+    }
+
+    if terminal {
+        let terminal_bin = resolve_terminal();
+        let mut wrapped = vec![terminal_bin.clone()];
+        if let Some(flag) = terminal_exec_flag(&terminal_bin) {
+            wrapped.push(flag.to_string());
+        }
+        wrapped.append(&mut argv);
+        argv = wrapped;
+    }
+
+    let program = argv.remove(0);
+    Some(LaunchSpec {
+        program,
+        args: argv,
+        working_dir: working_dir.map(|s| s.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unquote_splits_on_whitespace() {
+        assert_eq!(unquote_exec("vim --foo bar"), vec!["vim", "--foo", "bar"]);
+    }
+
+    #[test]
+    fn unquote_keeps_quoted_whitespace_together() {
+        assert_eq!(unquote_exec(r#"app "two words""#), vec!["app", "two words"]);
+    }
+
+    #[test]
+    fn unquote_only_honors_backslash_before_special_chars() {
+        assert_eq!(unquote_exec(r#""a\"b""#), vec!["a\"b"]);
+        assert_eq!(unquote_exec(r#""a\nb""#), vec!["a\\nb"]);
+    }
+
+    #[test]
+    fn expand_field_codes_drops_file_and_url_codes() {
+        let ctx = FieldCodeContext { name: "App", icon: None, desktop_file_path: "/x.desktop" };
+        let tokens = vec!["app".to_string(), "%f".to_string(), "%U".to_string()];
+        assert_eq!(expand_field_codes(tokens, &ctx), vec!["app"]);
+    }
+
+    #[test]
+    fn expand_field_codes_substitutes_icon_name_and_path() {
+        let ctx = FieldCodeContext { name: "App", icon: Some("app-icon"), desktop_file_path: "/x.desktop" };
+        let tokens = vec!["app".to_string(), "%i".to_string(), "%c".to_string(), "%k".to_string()];
+        assert_eq!(
+            expand_field_codes(tokens, &ctx),
+            vec!["app", "--icon", "app-icon", "App", "/x.desktop"]
+        );
+    }
+
+    #[test]
+    fn terminal_exec_flag_special_cases_gnome_terminal_and_kitty() {
+        assert_eq!(terminal_exec_flag("/usr/bin/gnome-terminal"), Some("--"));
+        assert_eq!(terminal_exec_flag("kitty"), None);
+        assert_eq!(terminal_exec_flag("alacritty"), Some("-e"));
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// On-disk representation of a single app's launch history. Kept separate
+/// from the in-memory `(u32, SystemTime)` pair since `SystemTime` has no
+/// stable serde mapping; `last_launched_secs` is a Unix timestamp.
+#[derive(Serialize, Deserialize)]
+struct HistoryEntryWire {
+    count: u32,
+    last_launched_secs: u64,
+}
+
+/// Persisted launch history, used to rank apps by "frecency" (launch
+/// frequency weighted by recency) the way GNOME's favorites and
+/// pop-shell's launcher surface common apps. Keyed by the app's
+/// desktop-file id, falling back to its `Exec` line when no id is known.
+pub struct HistoryStore {
+    entries: HashMap<String, (u32, SystemTime)>,
+    path: PathBuf,
+}
+
+fn history_path() -> PathBuf {
+    let data_home = std::env::var("XDG_DATA_HOME").unwrap_or_else(|_| {
+        format!("{}/.local/share", std::env::var("HOME").unwrap_or_default())
+    });
+    PathBuf::from(data_home).join("lacker").join("history")
+}
+
+impl HistoryStore {
+    /// Loads the history store from `$XDG_DATA_HOME/lacker/history`,
+    /// starting empty if the file doesn't exist yet or fails to parse.
+    pub fn load() -> Self {
+        let path = history_path();
+        let wire: HashMap<String, HistoryEntryWire> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let entries = wire
+            .into_iter()
+            .map(|(key, entry)| {
+                let last_launched = UNIX_EPOCH + Duration::from_secs(entry.last_launched_secs);
+                (key, (entry.count, last_launched))
+            })
+            .collect();
+
+        HistoryStore { entries, path }
+    }
+
+    /// Records a launch of `key` (the app's desktop-file id, or its `exec`
+    /// as a fallback) and persists the updated history to disk.
+    pub fn record(&mut self, key: &str) {
+        let entry = self.entries.entry(key.to_string()).or_insert((0, UNIX_EPOCH));
+        entry.0 += 1;
+        entry.1 = SystemTime::now();
+
+        let _ = self.save();
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let wire: HashMap<&String, HistoryEntryWire> = self
+            .entries
+            .iter()
+            .map(|(key, (count, last_launched))| {
+                let last_launched_secs = last_launched
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                (key, HistoryEntryWire { count: *count, last_launched_secs })
+            })
+            .collect();
+
+        let json = serde_json::to_string_pretty(&wire).unwrap_or_default();
+        fs::write(&self.path, json)
+    }
+
+    /// Scores `key` by recency-weighted launch frequency: `count *
+    /// weight(age)`, where `weight` buckets the time since last launch
+    /// (today = 4, this week = 2, this month = 1, older = 0.5). Apps never
+    /// launched score 0.
+    pub fn frecency(&self, key: &str) -> f64 {
+        let Some((count, last_launched)) = self.entries.get(key) else {
+            return 0.0;
+        };
+
+        let age = SystemTime::now().duration_since(*last_launched).unwrap_or_default();
+        let weight = if age < Duration::from_secs(24 * 60 * 60) {
+            4.0
+        } else if age < Duration::from_secs(7 * 24 * 60 * 60) {
+            2.0
+        } else if age < Duration::from_secs(30 * 24 * 60 * 60) {
+            1.0
+        } else {
+            0.5
+        };
+
+        *count as f64 * weight
+    }
+
+    /// Returns up to `n` keys with non-zero frecency, ranked highest first.
+    pub fn top_keys(&self, n: usize) -> Vec<String> {
+        let mut ranked: Vec<(&String, f64)> = self
+            .entries
+            .keys()
+            .map(|key| (key, self.frecency(key)))
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ranked.into_iter().take(n).map(|(key, _)| key.clone()).collect()
+    }
+}
@@ -1,18 +1,76 @@
 use gtk4::prelude::*;
 use gtk4::glib;
-use gtk4::{Application, ApplicationWindow, Box, Button, Image, Label, Orientation, ScrolledWindow, SearchEntry, Separator};
+use gtk4::{gio, Application, ApplicationWindow, Box, Button, GestureClick, GestureLongPress, Image, Label, Orientation, PopoverMenu, ScrolledWindow, SearchEntry, Separator};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fs;
+use std::rc::Rc;
 
 #[cfg(feature = "wayland")]
 use gtk4_layer_shell::{Edge, Layer, LayerShell};
 
+mod calc;
+mod dbus_service;
+mod exec;
+mod history;
+mod wm;
+
+use history::HistoryStore;
+use wm::RunningWindow;
+
 #[derive(Clone, Debug)]
 struct DesktopApp {
+    id: String,
     name: String,
+    generic_name: Option<String>,
     exec: String,
     icon: Option<String>,
     categories: Vec<String>,
+    /// Whether `Terminal=true` is set, meaning `exec` must run inside a
+    /// terminal emulator rather than being spawned directly.
+    terminal: bool,
+    /// The `Path=` working directory, if set.
+    working_dir: Option<String>,
+    /// The `.desktop` file's own path, substituted for the `%k` field code.
+    desktop_file_path: String,
+    /// Additional `Actions=` entries, e.g. "New Window"/"New Private Window".
+    actions: Vec<DesktopAction>,
+}
+
+impl DesktopApp {
+    /// The key used to look up this app's launch history: its desktop-file
+    /// id when known, falling back to its `Exec` line.
+    fn history_key(&self) -> &str {
+        if self.id.is_empty() {
+            &self.exec
+        } else {
+            &self.id
+        }
+    }
+}
+
+/// A single `[Desktop Action <id>]` group: an additional entry point into
+/// an app, such as "New Window" or "Compose Message".
+#[derive(Clone, Debug)]
+struct DesktopAction {
+    name: String,
+    exec: String,
+}
+
+/// A single entry in the merged, searchable list: either an installed app
+/// that gets launched, or a currently open window that gets focused.
+enum SearchResult<'a> {
+    App(&'a DesktopApp),
+    Window(RunningWindow),
+}
+
+impl SearchResult<'_> {
+    fn name(&self) -> &str {
+        match self {
+            SearchResult::App(app) => &app.name,
+            SearchResult::Window(window) => &window.title,
+        }
+    }
 }
 
 fn scan_applications() -> Vec<DesktopApp> {
@@ -36,7 +94,9 @@ fn scan_applications() -> Vec<DesktopApp> {
                                     continue;
                                 }
 
+                                let id = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
                                 let name = de.name(None).unwrap_or(std::borrow::Cow::Borrowed("Unknown")).to_string();
+                                let generic_name = de.generic_name(None).map(|s| s.to_string());
                                 let icon = de.icon().map(|s: &str| s.to_string());
                                 let categories: Vec<String> = de.categories()
                                     .unwrap_or_default()
@@ -44,12 +104,31 @@ fn scan_applications() -> Vec<DesktopApp> {
                                     .filter(|s: &&str| !s.is_empty())
                                     .map(|s: &str| s.to_string())
                                     .collect();
+                                let terminal = de.terminal();
+                                let working_dir = de.desktop_entry("Path").map(|s| s.to_string());
+                                let desktop_file_path = path.to_string_lossy().to_string();
+                                let actions: Vec<DesktopAction> = de.actions()
+                                    .unwrap_or_default()
+                                    .split(';')
+                                    .filter(|action_id: &&str| !action_id.is_empty())
+                                    .filter_map(|action_id| {
+                                        let name = de.action_entry(action_id, "Name")?;
+                                        let exec = de.action_entry(action_id, "Exec")?;
+                                        Some(DesktopAction { name: name.to_string(), exec: exec.to_string() })
+                                    })
+                                    .collect();
 
                                 apps.push(DesktopApp {
+                                    id,
                                     name,
+                                    generic_name,
                                     exec: exec.to_string(),
                                     icon,
                                     categories,
+                                    terminal,
+                                    working_dir,
+                                    desktop_file_path,
+                                    actions,
                                 });
                             }
                         }
@@ -83,21 +162,217 @@ fn categorize_apps(apps: &[DesktopApp]) -> HashMap<String, Vec<DesktopApp>> {
     categories
 }
 
-fn launch_app(exec: &str) {
-    let exec = exec.split_whitespace()
-        .filter(|s| !s.starts_with('%'))
-        .collect::<Vec<_>>()
-        .join(" ");
+/// Scores `haystack` against `query` by matching each whitespace-separated
+/// word of `query` as its own ordered, not-necessarily-contiguous
+/// subsequence of `haystack`, so multi-word queries like "gimp img" find
+/// "GNU Image Manipulation Program" even though "gimp" and "img" only
+/// appear out of order relative to each other. Returns `None` if any word
+/// can't be matched at all, otherwise the sum of the per-word scores.
+fn fuzzy_match(query: &str, haystack: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    query.split_whitespace().map(|word| fuzzy_match_word(word, haystack)).sum()
+}
+
+/// Scores `haystack` against a single `word` as an ordered,
+/// not-necessarily-contiguous subsequence match. Returns `None` if some
+/// character of `word` is missing from the haystack, otherwise a score
+/// where higher means a better match: big bonuses for matching at the
+/// start of the haystack or right after a separator, a bonus for runs of
+/// consecutive matched characters, and a penalty for gap characters
+/// skipped between matches.
+fn fuzzy_match_word(word: &str, haystack: &str) -> Option<i64> {
+    let word_chars: Vec<char> = word.chars().collect();
+    let hay_chars: Vec<char> = haystack.chars().collect();
+
+    let mut score: i64 = 0;
+    let mut wi = 0;
+    let mut last_match: Option<usize> = None;
+    let mut run = 0i64;
+
+    for (hi, &hc) in hay_chars.iter().enumerate() {
+        if wi >= word_chars.len() {
+            break;
+        }
+        if hc != word_chars[wi] {
+            continue;
+        }
+
+        if hi == 0 {
+            score += 100;
+        } else if matches!(hay_chars[hi - 1], ' ' | '-' | '_') {
+            score += 60;
+        }
+
+        match last_match {
+            Some(last) if hi == last + 1 => {
+                run += 1;
+                score += 15 * run;
+            }
+            Some(last) => {
+                run = 0;
+                score -= (hi - last - 1) as i64 * 2;
+            }
+            None => run = 0,
+        }
+
+        last_match = Some(hi);
+        wi += 1;
+    }
+
+    if wi == word_chars.len() {
+        Some(score)
+    } else {
+        None
+    }
+}
 
+/// Scores an app against a (lowercased) query by fuzzy-matching its name,
+/// generic name, and categories, returning the best match found. The name
+/// is the primary field; generic name and categories are demoted a bit so a
+/// direct name match still wins ties. The app's frecency is folded in as a
+/// small tie-breaker bonus so habitually-used apps win close matches.
+fn score_app(app: &DesktopApp, query_lower: &str, history: &HistoryStore) -> Option<i64> {
+    let mut best: Option<i64> = fuzzy_match(query_lower, &app.name.to_lowercase());
+
+    if let Some(generic_name) = &app.generic_name {
+        if let Some(score) = fuzzy_match(query_lower, &generic_name.to_lowercase()) {
+            let score = score - 10;
+            best = Some(best.map_or(score, |b| b.max(score)));
+        }
+    }
+
+    for category in &app.categories {
+        if let Some(score) = fuzzy_match(query_lower, &category.to_lowercase()) {
+            let score = score - 20;
+            best = Some(best.map_or(score, |b| b.max(score)));
+        }
+    }
+
+    best.map(|score| score + (history.frecency(app.history_key()) * 2.0) as i64)
+}
+
+/// Scores a running window against a (lowercased) query by fuzzy-matching
+/// its title and window class, so search spans both installed apps and
+/// live windows. Title is the primary field; class is demoted a bit.
+fn score_window(window: &RunningWindow, query_lower: &str) -> Option<i64> {
+    let mut best: Option<i64> = fuzzy_match(query_lower, &window.title.to_lowercase());
+
+    if let Some(score) = fuzzy_match(query_lower, &window.class.to_lowercase()) {
+        let score = score - 10;
+        best = Some(best.map_or(score, |b| b.max(score)));
+    }
+
+    best
+}
+
+/// Finds the icon of the installed app whose id or name best matches a
+/// running window's class/app_id, so window entries can reuse the same
+/// icon lookup as installed apps.
+fn icon_for_window(window: &RunningWindow, apps: &[DesktopApp]) -> Option<String> {
+    let class_lower = window.class.to_lowercase();
+    apps.iter()
+        .find(|app| app.id.to_lowercase() == class_lower || app.name.to_lowercase() == class_lower)
+        .or_else(|| apps.iter().find(|app| app.name.to_lowercase().contains(&class_lower)))
+        .and_then(|app| app.icon.clone())
+}
+
+/// Spawns an already-resolved command, optionally in its working directory.
+fn spawn_launch_spec(spec: exec::LaunchSpec) {
     std::thread::spawn(move || {
-        let _ = std::process::Command::new("sh")
-            .arg("-c")
-            .arg(&exec)
-            .spawn();
+        let mut command = std::process::Command::new(&spec.program);
+        command.args(&spec.args);
+        if let Some(working_dir) = &spec.working_dir {
+            command.current_dir(working_dir);
+        }
+        let _ = command.spawn();
     });
 }
 
-fn create_app_button(app: &DesktopApp) -> Button {
+/// Launches an app's `Exec=` line, recording it in the launch history.
+/// Parses and expands the Exec field per the Desktop Entry spec and spawns
+/// the resulting argv directly (no `sh -c`), wrapping it in the user's
+/// terminal emulator when the entry has `Terminal=true`.
+fn launch_app(app: &DesktopApp, history: &Rc<RefCell<HistoryStore>>) {
+    history.borrow_mut().record(app.history_key());
+
+    if let Some(spec) = exec::build_launch_spec(
+        &app.exec,
+        &app.name,
+        app.icon.as_deref(),
+        &app.desktop_file_path,
+        app.terminal,
+        app.working_dir.as_deref(),
+    ) {
+        spawn_launch_spec(spec);
+    }
+}
+
+/// Launches one of an app's `Desktop Action` entries instead of its
+/// primary `Exec=` line, e.g. "New Private Window".
+fn launch_action(app: &DesktopApp, action: &DesktopAction, history: &Rc<RefCell<HistoryStore>>) {
+    history.borrow_mut().record(app.history_key());
+
+    if let Some(spec) = exec::build_launch_spec(
+        &action.exec,
+        &app.name,
+        app.icon.as_deref(),
+        &app.desktop_file_path,
+        app.terminal,
+        app.working_dir.as_deref(),
+    ) {
+        spawn_launch_spec(spec);
+    }
+}
+
+/// Attaches a secondary-click (and long-press, for touch) gesture to
+/// `button` that pops up a `PopoverMenu` listing `app`'s Desktop Actions,
+/// each invoking `launch_action`. No-op if the app has no actions.
+fn attach_actions_popover(button: &Button, app: &DesktopApp, history: &Rc<RefCell<HistoryStore>>) {
+    if app.actions.is_empty() {
+        return;
+    }
+
+    let menu = gio::Menu::new();
+    let action_group = gio::SimpleActionGroup::new();
+
+    for (index, action) in app.actions.iter().enumerate() {
+        let action_name = format!("action-{index}");
+        menu.append(Some(&action.name), Some(&format!("desktop-actions.{action_name}")));
+
+        let simple_action = gio::SimpleAction::new(&action_name, None);
+        let app_clone = app.clone();
+        let action_clone = action.clone();
+        let history = history.clone();
+        simple_action.connect_activate(move |_, _| {
+            launch_action(&app_clone, &action_clone, &history);
+        });
+        action_group.add_action(&simple_action);
+    }
+
+    button.insert_action_group("desktop-actions", Some(&action_group));
+
+    let popover = PopoverMenu::from_model(Some(&menu));
+    popover.set_parent(button);
+
+    let secondary_click = GestureClick::new();
+    secondary_click.set_button(gtk4::gdk::BUTTON_SECONDARY);
+    let popover_for_click = popover.clone();
+    secondary_click.connect_pressed(move |_, _, _, _| {
+        popover_for_click.popup();
+    });
+    button.add_controller(secondary_click);
+
+    let long_press = GestureLongPress::new();
+    long_press.connect_pressed(move |_, _, _| {
+        popover.popup();
+    });
+    button.add_controller(long_press);
+}
+
+fn create_app_button(app: &DesktopApp, history: &Rc<RefCell<HistoryStore>>) -> Button {
     let button_box = Box::new(Orientation::Horizontal, 8);
     button_box.set_margin_start(4);
     button_box.set_margin_end(4);
@@ -119,20 +394,122 @@ fn create_app_button(app: &DesktopApp) -> Button {
     button.set_child(Some(&button_box));
     button.set_has_frame(false);
 
-    let exec = app.exec.clone();
+    attach_actions_popover(&button, app, history);
+
+    let app_clone = app.clone();
+    let history = history.clone();
+    button.connect_clicked(move |_| {
+        launch_app(&app_clone, &history);
+    });
+
+    button
+}
+
+/// Builds a button for a currently open window, matching the styling of
+/// `create_app_button`. Clicking it focuses the window instead of
+/// launching a new instance.
+fn create_window_button(window: &RunningWindow, icon_name: Option<&str>) -> Button {
+    let button_box = Box::new(Orientation::Horizontal, 8);
+    button_box.set_margin_start(4);
+    button_box.set_margin_end(4);
+    button_box.set_margin_top(2);
+    button_box.set_margin_bottom(2);
+
+    let icon = Image::from_icon_name(icon_name.unwrap_or("window-new-symbolic"));
+    icon.set_pixel_size(24);
+    button_box.append(&icon);
+
+    let label = Label::new(Some(&window.title));
+    label.set_xalign(0.0);
+    label.set_hexpand(true);
+    button_box.append(&label);
+
+    let running_label = Label::new(Some("running"));
+    running_label.add_css_class("dim-label");
+    button_box.append(&running_label);
+
+    let button = Button::new();
+    button.set_child(Some(&button_box));
+    button.set_has_frame(false);
+
+    let window = window.clone();
     button.connect_clicked(move |_| {
-        launch_app(&exec);
+        wm::focus_window(&window);
     });
-    
+
     button
 }
 
-fn rebuild_app_list(apps_box: &Box, apps: &[DesktopApp], categories: &HashMap<String, Vec<DesktopApp>>, query: &str) {
+/// Builds the special answer row shown when the query looks like an
+/// arithmetic expression or unit conversion, reusing `create_app_button`'s
+/// styling. Clicking it copies the computed value to the clipboard and
+/// closes the launcher.
+fn create_calc_button(result: &calc::CalcResult) -> Button {
+    let button_box = Box::new(Orientation::Horizontal, 8);
+    button_box.set_margin_start(4);
+    button_box.set_margin_end(4);
+    button_box.set_margin_top(2);
+    button_box.set_margin_bottom(2);
+
+    let icon = Image::from_icon_name("accessories-calculator-symbolic");
+    icon.set_pixel_size(24);
+    button_box.append(&icon);
+
+    let label = Label::new(Some(&format!("= {}", result.display)));
+    label.set_xalign(0.0);
+    label.set_hexpand(true);
+    button_box.append(&label);
+
+    let button = Button::new();
+    button.set_child(Some(&button_box));
+    button.set_has_frame(false);
+    button.add_css_class("suggested-action");
+
+    let value = result.value.clone();
+    button.connect_clicked(move |button| {
+        button.display().clipboard().set_text(&value);
+        if let Some(window) = button.root().and_downcast::<ApplicationWindow>() {
+            window.set_visible(false);
+        }
+    });
+
+    button
+}
+
+/// Number of apps shown in the "Frequent" section when the search is empty.
+const FREQUENT_APP_COUNT: usize = 6;
+
+fn rebuild_app_list(
+    apps_box: &Box,
+    apps: &[DesktopApp],
+    categories: &HashMap<String, Vec<DesktopApp>>,
+    query: &str,
+    history: &Rc<RefCell<HistoryStore>>,
+) {
     while let Some(child) = apps_box.first_child() {
         apps_box.remove(&child);
     }
 
     if query.is_empty() {
+        let frequent_keys = history.borrow().top_keys(FREQUENT_APP_COUNT);
+        let frequent_apps: Vec<&DesktopApp> = frequent_keys.iter()
+            .filter_map(|key| apps.iter().find(|app| app.history_key() == key))
+            .collect();
+
+        if !frequent_apps.is_empty() {
+            let cat_label = Label::new(Some("Frequent"));
+            cat_label.set_halign(gtk4::Align::Start);
+            cat_label.set_margin_start(12);
+            cat_label.set_margin_top(12);
+            cat_label.set_margin_bottom(4);
+            cat_label.add_css_class("heading");
+            apps_box.append(&cat_label);
+
+            for app in &frequent_apps {
+                apps_box.append(&create_app_button(app, history));
+            }
+        }
+
         let priority_cats = vec![
             ("Utilities", "Utility"),
             ("Development", "Development"),
@@ -155,34 +532,54 @@ fn rebuild_app_list(apps_box: &Box, apps: &[DesktopApp], categories: &HashMap<St
                     apps_box.append(&cat_label);
 
                     for app in cat_apps.iter().take(8) {
-                        apps_box.append(&create_app_button(app));
+                        apps_box.append(&create_app_button(app, history));
                     }
                 }
             }
         }
     } else {
+        if calc::looks_like_expression(query) {
+            if let Some(result) = calc::evaluate(query) {
+                apps_box.append(&create_calc_button(&result));
+            }
+        }
+
         let query_lower = query.to_lowercase();
-        let mut found_apps: Vec<&DesktopApp> = apps.iter()
-            .filter(|app| app.name.to_lowercase().contains(&query_lower))
+        let history_ref = history.borrow();
+        let found_apps: Vec<(&DesktopApp, i64)> = apps.iter()
+            .filter_map(|app| score_app(app, &query_lower, &history_ref).map(|score| (app, score)))
             .collect();
+        drop(history_ref);
 
-        found_apps.sort_by_key(|app| {
-            let name_lower = app.name.to_lowercase();
-            if name_lower.starts_with(&query_lower) {
-                0
-            } else {
-                name_lower.find(&query_lower).unwrap_or(usize::MAX)
-            }
+        let found_windows: Vec<(RunningWindow, i64)> = wm::list_windows().into_iter()
+            .filter_map(|window| score_window(&window, &query_lower).map(|score| (window, score)))
+            .collect();
+
+        // Merge apps and windows into one ranked list so search spans both.
+        let mut results: Vec<(SearchResult, i64)> = found_apps.into_iter()
+            .map(|(app, score)| (SearchResult::App(app), score))
+            .chain(found_windows.into_iter().map(|(window, score)| (SearchResult::Window(window), score)))
+            .collect();
+
+        results.sort_by(|(a, a_score), (b, b_score)| {
+            b_score.cmp(a_score).then_with(|| a.name().to_lowercase().cmp(&b.name().to_lowercase()))
         });
 
-        if found_apps.is_empty() {
+        if results.is_empty() {
             let no_results = Label::new(Some("No applications found"));
             no_results.set_margin_top(20);
             no_results.add_css_class("dim-label");
             apps_box.append(&no_results);
         } else {
-            for app in found_apps.iter().take(20) {
-                apps_box.append(&create_app_button(app));
+            for (result, _) in results.iter().take(20) {
+                let button = match result {
+                    SearchResult::App(app) => create_app_button(app, history),
+                    SearchResult::Window(window) => {
+                        let icon = icon_for_window(window, apps);
+                        create_window_button(window, icon.as_deref())
+                    }
+                };
+                apps_box.append(&button);
             }
         }
     }
@@ -216,7 +613,7 @@ fn setup_window_positioning(window: &ApplicationWindow) {
     // The window will appear as a normal window that cam be positioned by the WM
 }
 
-fn build_ui(app: &Application) {
+fn build_ui(app: &Application, receiver: glib::Receiver<dbus_service::LauncherCommand>) {
     let window = ApplicationWindow::builder()
         .application(app)
         .title("Deskbar")
@@ -276,18 +673,22 @@ fn build_ui(app: &Application) {
 
     let apps_box = Box::new(Orientation::Vertical, 0);
 
-    let apps = scan_applications();
-    let categories = categorize_apps(&apps);
+    // Kept resident between `Show`/`Hide`/`Toggle` so toggling the window
+    // from the DBus service is instant instead of rescanning every time.
+    let apps = Rc::new(RefCell::new(scan_applications()));
+    let categories = Rc::new(RefCell::new(categorize_apps(&apps.borrow())));
+    let history = Rc::new(RefCell::new(HistoryStore::load()));
 
-    rebuild_app_list(&apps_box, &apps, &categories, "");
+    rebuild_app_list(&apps_box, &apps.borrow(), &categories.borrow(), "", &history);
 
     // Search functionality
     let apps_box_clone = apps_box.clone();
     let all_apps = apps.clone();
     let all_categories = categories.clone();
+    let history_clone = history.clone();
     search_entry.connect_search_changed(move |entry| {
         let query = entry.text().to_string();
-        rebuild_app_list(&apps_box_clone, &all_apps, &all_categories, &query);
+        rebuild_app_list(&apps_box_clone, &all_apps.borrow(), &all_categories.borrow(), &query, &history_clone);
     });
 
     scrolled.set_child(Some(&apps_box));
@@ -343,13 +744,81 @@ fn build_ui(app: &Application) {
 
     window.set_child(Some(&main_box));
     window.present();
+
+    // Act on Show/Hide/Toggle/Quit/Rescan calls coming in from the DBus
+    // service thread.
+    let app_for_commands = app.clone();
+    receiver.attach(None, move |command| {
+        match command {
+            dbus_service::LauncherCommand::Show => {
+                search_entry.set_text("");
+                window.present();
+            }
+            dbus_service::LauncherCommand::Hide => window.set_visible(false),
+            dbus_service::LauncherCommand::Toggle => {
+                if window.is_visible() {
+                    window.set_visible(false);
+                } else {
+                    search_entry.set_text("");
+                    window.present();
+                }
+            }
+            dbus_service::LauncherCommand::Quit => app_for_commands.quit(),
+            dbus_service::LauncherCommand::Rescan => {
+                *apps.borrow_mut() = scan_applications();
+                *categories.borrow_mut() = categorize_apps(&apps.borrow());
+                rebuild_app_list(&apps_box, &apps.borrow(), &categories.borrow(), &search_entry.text(), &history);
+            }
+        }
+        glib::ControlFlow::Continue
+    });
 }
 
 fn main() {
+    let (sender, receiver) = glib::MainContext::channel(glib::Priority::DEFAULT);
+
+    // Single-instance: if another lacker is already running, just toggle
+    // its visibility and exit instead of rescanning everything again.
+    if !dbus_service::acquire_or_toggle_existing(sender) {
+        return;
+    }
+
+    // NON_UNIQUE: uniqueness is handled by our own DBus service above, which
+    // owns this same well-known name. Letting GApplication also try to
+    // register it would make `register()` fail, since it expects whatever
+    // owns the name to speak org.freedesktop.Application, not our Launcher
+    // interface.
     let app = Application::builder()
         .application_id("dpdns.org.pisekpiskovec.lacker")
+        .flags(gio::ApplicationFlags::NON_UNIQUE)
         .build();
 
-    app.connect_activate(build_ui);
+    let receiver = RefCell::new(Some(receiver));
+    app.connect_activate(move |app| {
+        if let Some(receiver) = receiver.borrow_mut().take() {
+            build_ui(app, receiver);
+        }
+    });
     app.run();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_each_query_word_independently() {
+        assert!(fuzzy_match("gimp img", "gnu image manipulation program").is_some());
+        assert!(fuzzy_match("ff", "firefox").is_some());
+    }
+
+    #[test]
+    fn fails_when_a_word_has_no_match() {
+        assert!(fuzzy_match("gimp zzz", "gnu image manipulation program").is_none());
+    }
+
+    #[test]
+    fn empty_query_matches_everything_with_no_bonus() {
+        assert_eq!(fuzzy_match("", "anything"), Some(0));
+    }
+}
@@ -0,0 +1,222 @@
+use serde::Deserialize;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// How long to wait on a single read/write against a compositor IPC
+/// socket before giving up, so a slow or unresponsive compositor can't
+/// freeze the launcher UI mid-keystroke.
+const SOCKET_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// A currently open window, surfaced by the compositor's IPC protocol so it
+/// can be focused from the launcher instead of relaunching the app.
+#[derive(Clone, Debug)]
+pub struct RunningWindow {
+    /// Opaque handle used to focus the window: a Hyprland address
+    /// (`0x...`) or a Sway/i3 container id, stringified.
+    pub id: String,
+    pub class: String,
+    pub title: String,
+    pub workspace: String,
+}
+
+/// Which compositor IPC (if any) this session talks to, detected once on
+/// first use and cached — `sway_socket_path` can shell out to
+/// `i3 --get-socketpath`, which is too slow to redo on every keystroke.
+enum Compositor {
+    Hyprland(String),
+    Sway(String),
+    None,
+}
+
+static COMPOSITOR: OnceLock<Compositor> = OnceLock::new();
+
+fn compositor() -> &'static Compositor {
+    COMPOSITOR.get_or_init(|| {
+        if let Some(socket_path) = hyprland_socket_path() {
+            return Compositor::Hyprland(socket_path);
+        }
+        if let Some(socket_path) = sway_socket_path() {
+            return Compositor::Sway(socket_path);
+        }
+        Compositor::None
+    })
+}
+
+#[derive(Deserialize)]
+struct HyprlandWorkspace {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct HyprlandClient {
+    address: String,
+    class: String,
+    title: String,
+    workspace: HyprlandWorkspace,
+}
+
+fn hyprland_socket_path() -> Option<String> {
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").ok()?;
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+    Some(format!("{runtime_dir}/hypr/{signature}/.socket.sock"))
+}
+
+fn hyprland_request(socket_path: &str, command: &str) -> Option<String> {
+    let mut stream = UnixStream::connect(socket_path).ok()?;
+    stream.set_read_timeout(Some(SOCKET_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(SOCKET_TIMEOUT)).ok()?;
+    stream.write_all(command.as_bytes()).ok()?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    Some(response)
+}
+
+fn list_hyprland_windows(socket_path: &str) -> Vec<RunningWindow> {
+    let Some(response) = hyprland_request(socket_path, "j/clients") else {
+        return Vec::new();
+    };
+
+    let Ok(clients) = serde_json::from_str::<Vec<HyprlandClient>>(&response) else {
+        return Vec::new();
+    };
+
+    clients.into_iter()
+        .map(|c| RunningWindow {
+            id: c.address,
+            class: c.class,
+            title: c.title,
+            workspace: c.workspace.name,
+        })
+        .collect()
+}
+
+fn focus_hyprland_window(socket_path: &str, window: &RunningWindow) {
+    hyprland_request(socket_path, &format!("dispatch focuswindow address:{}", window.id));
+}
+
+const I3_MAGIC: &[u8; 6] = b"i3-ipc";
+const I3_MESSAGE_TYPE_RUN_COMMAND: u32 = 0;
+const I3_MESSAGE_TYPE_GET_TREE: u32 = 4;
+
+fn sway_socket_path() -> Option<String> {
+    if let Ok(path) = std::env::var("SWAYSOCK") {
+        return Some(path);
+    }
+
+    let output = std::process::Command::new("i3").arg("--get-socketpath").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Sends an i3 IPC message (magic + little-endian length + little-endian
+/// type + payload) and returns the payload of the reply.
+fn i3_ipc_request(socket_path: &str, message_type: u32, payload: &str) -> Option<Vec<u8>> {
+    let mut stream = UnixStream::connect(socket_path).ok()?;
+    stream.set_read_timeout(Some(SOCKET_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(SOCKET_TIMEOUT)).ok()?;
+
+    let mut request = Vec::with_capacity(14 + payload.len());
+    request.extend_from_slice(I3_MAGIC);
+    request.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    request.extend_from_slice(&message_type.to_le_bytes());
+    request.extend_from_slice(payload.as_bytes());
+    stream.write_all(&request).ok()?;
+
+    let mut header = [0u8; 14];
+    stream.read_exact(&mut header).ok()?;
+    if &header[0..6] != I3_MAGIC {
+        return None;
+    }
+    let len = u32::from_le_bytes(header[6..10].try_into().ok()?) as usize;
+
+    let mut body = vec![0u8; len];
+    stream.read_exact(&mut body).ok()?;
+    Some(body)
+}
+
+#[derive(Deserialize)]
+struct I3WindowProperties {
+    class: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct I3Node {
+    id: i64,
+    name: Option<String>,
+    app_id: Option<String>,
+    window_properties: Option<I3WindowProperties>,
+    #[serde(default)]
+    nodes: Vec<I3Node>,
+    #[serde(default)]
+    floating_nodes: Vec<I3Node>,
+}
+
+/// Walks the i3/Sway container tree collecting leaf windows, tracking the
+/// name of the workspace node each one descends from.
+fn collect_i3_windows(node: &I3Node, workspace: &str, out: &mut Vec<RunningWindow>) {
+    let is_workspace = node.name.is_some() && node.app_id.is_none() && node.window_properties.is_none()
+        && (!node.nodes.is_empty() || !node.floating_nodes.is_empty());
+    let workspace = if is_workspace {
+        node.name.as_deref().unwrap_or(workspace)
+    } else {
+        workspace
+    };
+
+    let class = node.app_id.clone()
+        .or_else(|| node.window_properties.as_ref().and_then(|p| p.class.clone()));
+
+    if let Some(class) = class {
+        out.push(RunningWindow {
+            id: node.id.to_string(),
+            class,
+            title: node.name.clone().unwrap_or_default(),
+            workspace: workspace.to_string(),
+        });
+    }
+
+    for child in node.nodes.iter().chain(node.floating_nodes.iter()) {
+        collect_i3_windows(child, workspace, out);
+    }
+}
+
+fn list_sway_windows(socket_path: &str) -> Vec<RunningWindow> {
+    let Some(payload) = i3_ipc_request(socket_path, I3_MESSAGE_TYPE_GET_TREE, "") else {
+        return Vec::new();
+    };
+
+    let Ok(root) = serde_json::from_slice::<I3Node>(&payload) else {
+        return Vec::new();
+    };
+
+    let mut windows = Vec::new();
+    collect_i3_windows(&root, "", &mut windows);
+    windows
+}
+
+fn focus_sway_window(socket_path: &str, window: &RunningWindow) {
+    i3_ipc_request(socket_path, I3_MESSAGE_TYPE_RUN_COMMAND, &format!("[con_id={}] focus", window.id));
+}
+
+/// Lists the currently open windows via whichever compositor IPC is
+/// available, preferring Hyprland then Sway/i3. Returns an empty list
+/// outside a supported compositor or if the IPC call fails.
+pub fn list_windows() -> Vec<RunningWindow> {
+    match compositor() {
+        Compositor::Hyprland(socket_path) => list_hyprland_windows(socket_path),
+        Compositor::Sway(socket_path) => list_sway_windows(socket_path),
+        Compositor::None => Vec::new(),
+    }
+}
+
+/// Focuses `window` through whichever compositor IPC it was discovered on.
+pub fn focus_window(window: &RunningWindow) {
+    match compositor() {
+        Compositor::Hyprland(socket_path) => focus_hyprland_window(socket_path, window),
+        Compositor::Sway(socket_path) => focus_sway_window(socket_path, window),
+        Compositor::None => {}
+    }
+}